@@ -0,0 +1,114 @@
+//! Placeholder clients for the upstream DeepSeek and Anthropic APIs.
+//!
+//! Neither client sends an HTTP request: each `chat` returns a
+//! `not_implemented` provider error and each `chat_stream` yields an empty
+//! stream. The caller-supplied OpenRouter token and base URL are held but
+//! not used.
+
+use crate::{
+    error::{ApiError, Result},
+    models::{
+        AnthropicChatResponse, AnthropicStreamChunk, DeepSeekChatResponse, DeepSeekStreamChunk,
+        Message, ProviderConfig, Tool,
+    },
+};
+use futures::Stream;
+
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Client for the DeepSeek reasoning model, reached via OpenRouter.
+pub struct DeepSeekClient {
+    http: reqwest::Client,
+    token: String,
+}
+
+impl DeepSeekClient {
+    pub fn new(token: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            token,
+        }
+    }
+
+    /// Returns a `not_implemented` `DeepSeekError` without contacting
+    /// DeepSeek; `messages`, `config` and `tools` are accepted and ignored.
+    pub async fn chat(
+        &self,
+        messages: Vec<Message>,
+        config: &ProviderConfig,
+        tools: Option<&[Tool]>,
+    ) -> Result<DeepSeekChatResponse> {
+        let _ = (&self.http, &self.token, OPENROUTER_BASE_URL, messages, config, tools);
+        Err(ApiError::DeepSeekError {
+            message: "DeepSeek client not configured".to_string(),
+            type_: "not_implemented".to_string(),
+            param: None,
+            code: None,
+        })
+    }
+
+    pub fn chat_stream(
+        &self,
+        _messages: Vec<Message>,
+        _config: &ProviderConfig,
+        _tools: Option<&[Tool]>,
+    ) -> impl Stream<Item = Result<DeepSeekStreamChunk>> {
+        futures::stream::empty()
+    }
+}
+
+/// Client for the Anthropic response model, reached via OpenRouter.
+pub struct AnthropicClient {
+    http: reqwest::Client,
+    token: String,
+}
+
+impl AnthropicClient {
+    pub fn new(token: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            token,
+        }
+    }
+
+    /// Returns a `not_implemented` `AnthropicError` without contacting
+    /// Anthropic. `cache` is accepted and ignored: no `cache_control`
+    /// breakpoint is attached to the system prompt or the `<thinking>`
+    /// message.
+    pub async fn chat(
+        &self,
+        messages: Vec<Message>,
+        system: Option<String>,
+        config: &ProviderConfig,
+        tools: Option<&[Tool]>,
+        cache: bool,
+    ) -> Result<AnthropicChatResponse> {
+        let _ = (
+            &self.http,
+            &self.token,
+            OPENROUTER_BASE_URL,
+            messages,
+            system,
+            config,
+            tools,
+            cache,
+        );
+        Err(ApiError::AnthropicError {
+            message: "Anthropic client not configured".to_string(),
+            type_: "not_implemented".to_string(),
+            param: None,
+            code: None,
+        })
+    }
+
+    pub fn chat_stream(
+        &self,
+        _messages: Vec<Message>,
+        _system: Option<String>,
+        _config: &ProviderConfig,
+        _tools: Option<&[Tool]>,
+        _cache: bool,
+    ) -> impl Stream<Item = Result<AnthropicStreamChunk>> {
+        futures::stream::empty()
+    }
+}
@@ -0,0 +1,66 @@
+//! Runtime configuration for the API.
+//!
+//! `Config` is loaded once at startup and shared via `AppState`. It carries
+//! pricing information, used to translate token counts reported by upstream
+//! providers into a dollar cost, and the cap on candidates per request.
+
+/// Top-level application configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub pricing: PricingConfig,
+    /// Upper bound on the `n` parameter of `ApiRequest`, i.e. how many
+    /// parallel reasoning+response candidates a single client call may
+    /// request. Protects DeepSeek/Anthropic from a single caller fanning out
+    /// an unbounded number of upstream requests.
+    pub max_client_batch_size: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            pricing: PricingConfig {
+                deepseek: DeepSeekPricing {
+                    input_cache_hit_price: 0.014,
+                    input_cache_miss_price: 0.14,
+                    output_price: 0.28,
+                },
+                anthropic: AnthropicPricing {
+                    claude_3_sonnet: ModelPricing {
+                        input_price: 3.0,
+                        output_price: 15.0,
+                        cache_write_price: 3.75,
+                        cache_read_price: 0.3,
+                    },
+                },
+            },
+            max_client_batch_size: 4,
+        }
+    }
+}
+
+/// Per-provider pricing, expressed in dollars per million tokens.
+#[derive(Debug, Clone)]
+pub struct PricingConfig {
+    pub deepseek: DeepSeekPricing,
+    pub anthropic: AnthropicPricing,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeepSeekPricing {
+    pub input_cache_hit_price: f64,
+    pub input_cache_miss_price: f64,
+    pub output_price: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnthropicPricing {
+    pub claude_3_sonnet: ModelPricing,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelPricing {
+    pub input_price: f64,
+    pub output_price: f64,
+    pub cache_write_price: f64,
+    pub cache_read_price: f64,
+}
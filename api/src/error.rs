@@ -0,0 +1,87 @@
+//! Error types shared across the API.
+//!
+//! Every handler that can fail returns `Result<T>` so that failures from either upstream
+//! provider, or from malformed client input, are reported with a consistent
+//! JSON shape.
+
+use axum::{
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures::{stream::BoxStream, Stream, StreamExt};
+use serde::Serialize;
+use std::convert::Infallible;
+
+/// Convenience alias used throughout the crate.
+pub type Result<T> = std::result::Result<T, ApiError>;
+
+/// A boxed stream of SSE events, as produced by the chat pipeline.
+pub type EventStream = BoxStream<'static, std::result::Result<Event, Infallible>>;
+
+/// An SSE response backed by a boxed event stream.
+///
+/// Wrapping the raw `Sse<EventStream>` lets the bespoke `chat_stream` handler
+/// return a single named type instead of leaking the underlying stream
+/// implementation. The OpenAI-compatible handler formats the same pipeline
+/// events into its own chunk shape and returns a plain `Sse` instead.
+pub struct SseResponse(Sse<EventStream>);
+
+impl SseResponse {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = std::result::Result<Event, Infallible>> + Send + 'static,
+    {
+        Self(Sse::new(stream.boxed()))
+    }
+}
+
+impl IntoResponse for SseResponse {
+    fn into_response(self) -> Response {
+        self.0.into_response()
+    }
+}
+
+/// Errors that can occur while handling a chat request.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum ApiError {
+    /// A required header was not present on the request.
+    MissingHeader { header: String },
+    /// The request body failed validation.
+    BadRequest { message: String },
+    /// The supplied system prompt was invalid (e.g. empty or malformed).
+    InvalidSystemPrompt,
+    /// The DeepSeek API returned an error.
+    DeepSeekError {
+        message: String,
+        type_: String,
+        param: Option<String>,
+        code: Option<String>,
+    },
+    /// The Anthropic API returned an error.
+    AnthropicError {
+        message: String,
+        type_: String,
+        param: Option<String>,
+        code: Option<String>,
+    },
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::MissingHeader { .. } | ApiError::BadRequest { .. } => {
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            ApiError::InvalidSystemPrompt => axum::http::StatusCode::BAD_REQUEST,
+            ApiError::DeepSeekError { .. } | ApiError::AnthropicError { .. } => {
+                axum::http::StatusCode::BAD_GATEWAY
+            }
+        };
+
+        (status, Json(self)).into_response()
+    }
+}
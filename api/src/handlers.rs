@@ -10,17 +10,19 @@ use crate::{
     config::Config,
     error::{ApiError, Result, SseResponse},
     models::{
-        AnthropicUsage, ApiRequest, ApiResponse, CombinedUsage, ContentBlock, DeepSeekUsage,
-        ExternalApiResponse, Message, Role, StreamEvent,
+        AnthropicChatResponse, AnthropicUsage, ApiChoice, ApiRequest, ApiResponse, CombinedUsage,
+        ContentBlock, DeepSeekChatResponse, DeepSeekUsage, ExternalApiResponse, Message, Role,
+        StreamEvent, ToolCall,
     },
 };
 use axum::{
     extract::State,
     response::{sse::Event, IntoResponse},
-    Json,
+    routing::post,
+    Json, Router,
 };
 use chrono::Utc;
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, StreamExt};
 use std::{collections::HashMap, sync::Arc};
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -32,6 +34,14 @@ pub struct AppState {
     pub config: Config,
 }
 
+/// Builds the application's route table.
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/chat", post(handle_chat))
+        .merge(crate::openai::router())
+        .with_state(state)
+}
+
 /// Extracts API tokens from request headers.
 ///
 /// # Arguments
@@ -41,7 +51,7 @@ pub struct AppState {
 /// # Returns
 ///
 /// * `
-fn extract_api_tokens(headers: &axum::http::HeaderMap) -> Result<String> {
+pub(crate) fn extract_api_tokens(headers: &axum::http::HeaderMap) -> Result<String> {
     let openrouter_token = headers
         .get("X-OpenRouter-API-Token")
         .ok_or_else(|| ApiError::MissingHeader {
@@ -61,19 +71,21 @@ fn extract_api_tokens(headers: &axum::http::HeaderMap) -> Result<String> {
 ///
 /// * `input_tokens` - Number of input tokens processed
 /// * `output_tokens` - Number of output tokens generated
-/// * `_reasoning_tokens` - Number of tokens used for reasoning
 /// * `cached_tokens` - Number of tokens retrieved from cache
 /// * `config` - Configuration containing pricing information
 ///
 /// # Returns
 ///
 /// The total cost in dollars for the API usage
-fn calculate_deepseek_cost(
+pub(crate) fn calculate_deepseek_cost(
     input_tokens: u32,
     output_tokens: u32,
+    cached_tokens: u32,
     config: &Config,
 ) -> f64 {
-    let cached_tokens = 0; // Not currently used
+    // A cache-hit count in excess of the input total would be an upstream
+    // reporting glitch; clamp rather than underflow the subtraction below.
+    let cached_tokens = cached_tokens.min(input_tokens);
     let cache_hit_cost =
         (cached_tokens as f64 / 1_000_000.0) * config.pricing.deepseek.input_cache_hit_price;
     let cache_miss_cost = ((input_tokens - cached_tokens) as f64 / 1_000_000.0)
@@ -97,16 +109,15 @@ fn calculate_deepseek_cost(
 /// # Returns
 ///
 /// The total cost in dollars for the API usage
-fn calculate_anthropic_cost(
+pub(crate) fn calculate_anthropic_cost(
     input_tokens: u32,
     output_tokens: u32,
+    cache_write_tokens: u32,
+    cache_read_tokens: u32,
     config: &Config,
 ) -> f64 {
     let pricing = &config.pricing.anthropic.claude_3_sonnet;
 
-    let cache_write_tokens = 0; // Not currently used
-    let cache_read_tokens = 0; // Not currently used
-
     let input_cost = (input_tokens as f64 / 1_000_000.0) * pricing.input_price;
     let output_cost = (output_tokens as f64 / 1_000_000.0) * pricing.output_price;
     let cache_write_cost = (cache_write_tokens as f64 / 1_000_000.0) * pricing.cache_write_price;
@@ -180,26 +191,238 @@ pub(crate) async fn chat(
         return Err(ApiError::InvalidSystemPrompt);
     }
 
+    let n = request.candidate_count();
+    if n > state.config.max_client_batch_size {
+        return Err(ApiError::BadRequest {
+            message: format!(
+                "n={} exceeds max_client_batch_size={}",
+                n, state.config.max_client_batch_size
+            ),
+        });
+    }
+
     // Extract API tokens
     let openrouter_token = extract_api_tokens(&headers)?;
 
     // Initialize clients based on token type
     let deepseek_client = DeepSeekClient::new(openrouter_token.clone());
-
     let anthropic_client = AnthropicClient::new(openrouter_token);
 
-    // Get messages with system prompt
+    // Run the N candidates' pipelines concurrently rather than one at a time.
+    let mut pending = FuturesUnordered::new();
+    for index in 0..n {
+        let deepseek_client = &deepseek_client;
+        let anthropic_client = &anthropic_client;
+        let request = &request;
+        let config = &state.config;
+        pending.push(async move {
+            (
+                index,
+                run_candidate(deepseek_client, anthropic_client, request, config).await,
+            )
+        });
+    }
+
+    let mut candidates: Vec<(u32, CandidateResult)> = Vec::with_capacity(n as usize);
+    while let Some((index, result)) = pending.next().await {
+        candidates.push((index, result?));
+    }
+    candidates.sort_by_key(|(index, _)| *index);
+
+    // Candidate 0 populates the legacy single-candidate fields so existing
+    // callers that only look at `content`/`tool_calls` keep working.
+    let (_, primary) = &candidates[0];
+    let content = primary.content.clone();
+    let tool_calls = primary.tool_calls.clone();
+    let deepseek_response = request.verbose.then(|| ExternalApiResponse {
+        status: 200,
+        headers: HashMap::new(),
+        body: serde_json::to_value(&primary.deepseek_response).unwrap_or_default(),
+    });
+    let anthropic_response = request.verbose.then(|| ExternalApiResponse {
+        status: 200,
+        headers: HashMap::new(),
+        body: serde_json::to_value(&primary.anthropic_response).unwrap_or_default(),
+    });
+
+    // Merge per-candidate usage into the combined totals.
+    let (mut deepseek_usage, mut anthropic_usage, total_deepseek_cost, total_anthropic_cost) =
+        merge_candidate_usage(&candidates);
+    deepseek_usage.total_cost = format_cost(total_deepseek_cost);
+    anthropic_usage.total_cost = format_cost(total_anthropic_cost);
+
+    // Only surface the indexed `choices` array once more than one candidate
+    // was actually requested.
+    let choices = (n > 1).then(|| {
+        candidates
+            .iter()
+            .map(|(index, candidate)| ApiChoice {
+                index: *index,
+                content: candidate.content.clone(),
+                tool_calls: candidate.tool_calls.clone(),
+                finish_reason: "stop",
+            })
+            .collect()
+    });
+
+    // Build response
+    let response = ApiResponse {
+        created: Utc::now(),
+        content,
+        deepseek_response,
+        anthropic_response,
+        combined_usage: CombinedUsage {
+            total_cost: format_cost(total_deepseek_cost + total_anthropic_cost),
+            deepseek_usage,
+            anthropic_usage,
+        },
+        tool_calls,
+        choices,
+    };
+
+    Ok(Json(response))
+}
+
+/// Sums every candidate's DeepSeek/Anthropic token usage and cost into the
+/// combined totals returned to the caller.
+///
+/// # Arguments
+///
+/// * `candidates` - Every candidate's result, as produced by `run_candidate`
+///
+/// # Returns
+///
+/// `(deepseek_usage, anthropic_usage, total_deepseek_cost,
+/// total_anthropic_cost)`; the two `*_usage` structs' `total_cost` fields are
+/// left unset for the caller to fill in once it has formatted the combined
+/// cost too.
+fn merge_candidate_usage(candidates: &[(u32, CandidateResult)]) -> (DeepSeekUsage, AnthropicUsage, f64, f64) {
+    let mut deepseek_usage = DeepSeekUsage {
+        input_tokens: 0,
+        output_tokens: 0,
+        total_tokens: 0,
+        total_cost: String::new(),
+    };
+    let mut anthropic_usage = AnthropicUsage {
+        input_tokens: 0,
+        output_tokens: 0,
+        total_tokens: 0,
+        total_cost: String::new(),
+    };
+    let mut total_deepseek_cost = 0.0;
+    let mut total_anthropic_cost = 0.0;
+
+    for (_, candidate) in candidates {
+        deepseek_usage.input_tokens += candidate.deepseek_response.usage.prompt_tokens;
+        deepseek_usage.output_tokens += candidate.deepseek_response.usage.completion_tokens;
+        deepseek_usage.total_tokens += candidate.deepseek_response.usage.total_tokens;
+        total_deepseek_cost += candidate.deepseek_cost;
+
+        anthropic_usage.input_tokens += candidate.anthropic_response.usage.input_tokens.unwrap_or(0);
+        anthropic_usage.output_tokens +=
+            candidate.anthropic_response.usage.output_tokens.unwrap_or(0);
+        anthropic_usage.total_tokens += candidate.anthropic_response.usage.total_tokens;
+        total_anthropic_cost += candidate.anthropic_cost;
+    }
+
+    (deepseek_usage, anthropic_usage, total_deepseek_cost, total_anthropic_cost)
+}
+
+/// Merges every streamed candidate's already-formatted `CombinedUsage` into
+/// one combined total, for `chat_stream`'s `n`-candidate fan-in. Mirrors
+/// `merge_candidate_usage`'s summation, but each candidate's cost arrives
+/// pre-formatted as a `$`-prefixed string (it was built from `format_cost`
+/// inside `spawn_chat_pipeline`), so costs are parsed back out rather than
+/// summed directly.
+///
+/// # Arguments
+///
+/// * `usages` - Each candidate's final `Usage` event, one per candidate
+///
+/// # Returns
+///
+/// A single `CombinedUsage` with every field summed across candidates
+fn merge_streamed_usage(usages: &[CombinedUsage]) -> CombinedUsage {
+    fn parse_cost(cost: &str) -> f64 {
+        cost.trim_start_matches('$').parse().unwrap_or(0.0)
+    }
+
+    let mut deepseek_usage = DeepSeekUsage {
+        input_tokens: 0,
+        output_tokens: 0,
+        total_tokens: 0,
+        total_cost: String::new(),
+    };
+    let mut anthropic_usage = AnthropicUsage {
+        input_tokens: 0,
+        output_tokens: 0,
+        total_tokens: 0,
+        total_cost: String::new(),
+    };
+    let mut total_deepseek_cost = 0.0;
+    let mut total_anthropic_cost = 0.0;
+
+    for usage in usages {
+        deepseek_usage.input_tokens += usage.deepseek_usage.input_tokens;
+        deepseek_usage.output_tokens += usage.deepseek_usage.output_tokens;
+        deepseek_usage.total_tokens += usage.deepseek_usage.total_tokens;
+        total_deepseek_cost += parse_cost(&usage.deepseek_usage.total_cost);
+
+        anthropic_usage.input_tokens += usage.anthropic_usage.input_tokens;
+        anthropic_usage.output_tokens += usage.anthropic_usage.output_tokens;
+        anthropic_usage.total_tokens += usage.anthropic_usage.total_tokens;
+        total_anthropic_cost += parse_cost(&usage.anthropic_usage.total_cost);
+    }
+
+    deepseek_usage.total_cost = format_cost(total_deepseek_cost);
+    anthropic_usage.total_cost = format_cost(total_anthropic_cost);
+
+    CombinedUsage {
+        total_cost: format_cost(total_deepseek_cost + total_anthropic_cost),
+        deepseek_usage,
+        anthropic_usage,
+    }
+}
+
+/// The outcome of running one `n`-candidate pipeline to completion, as
+/// produced by `run_candidate`. Carries both providers' raw responses (for
+/// the `verbose` echo) alongside their already-extracted content and cost.
+struct CandidateResult {
+    content: Vec<ContentBlock>,
+    tool_calls: Option<Vec<ToolCall>>,
+    deepseek_response: DeepSeekChatResponse,
+    anthropic_response: AnthropicChatResponse,
+    deepseek_cost: f64,
+    anthropic_cost: f64,
+}
+
+/// Runs a single reasoning+response candidate: a DeepSeek call followed by
+/// an Anthropic call seeded with the resulting `<thinking>` block.
+///
+/// # Arguments
+///
+/// * `deepseek_client` - Client for the DeepSeek reasoning call
+/// * `anthropic_client` - Client for the Anthropic response call
+/// * `request` - The parsed chat request shared by every candidate
+/// * `config` - Configuration containing pricing information
+///
+/// # Returns
+///
+/// * `Result<CandidateResult>` - This candidate's content, tool calls, raw
+///   responses, and cost, or an error from either upstream call
+async fn run_candidate(
+    deepseek_client: &DeepSeekClient,
+    anthropic_client: &AnthropicClient,
+    request: &ApiRequest,
+    config: &Config,
+) -> Result<CandidateResult> {
     let messages = request.get_messages_with_system();
 
     // Call DeepSeek API
     let deepseek_response = deepseek_client
-        .chat(messages.clone(), &request.deepseek_config)
+        .chat(messages.clone(), &request.deepseek_config, request.tools.as_deref())
         .await?;
 
-    // Store response metadata
-    let deepseek_status: u16 = 200;
-    let deepseek_headers = HashMap::new(); // Headers not available when using high-level chat method
-
     // Extract reasoning content and wrap in thinking tags
     let reasoning_content = deepseek_response
         .choices
@@ -227,90 +450,113 @@ pub(crate) async fn chat(
             anthropic_messages,
             request.get_system_prompt().map(String::from),
             &request.anthropic_config,
+            request.tools.as_deref(),
+            request.cache,
         )
         .await?;
 
-    // Store response metadata
-    let anthropic_status: u16 = 200;
-    let anthropic_headers = HashMap::new(); // Headers not available when using high-level chat method
-
     // Calculate usage costs
     let deepseek_cost = calculate_deepseek_cost(
         deepseek_response.usage.prompt_tokens,
         deepseek_response.usage.completion_tokens,
-        &state.config,
+        deepseek_response.usage.cached_tokens,
+        config,
     );
 
     let anthropic_cost = calculate_anthropic_cost(
         anthropic_response.usage.prompt_tokens,
         anthropic_response.usage.completion_tokens,
-        &state.config,
+        anthropic_response.usage.cache_creation_input_tokens,
+        anthropic_response.usage.cache_read_input_tokens,
+        config,
     );
 
-    // Extract Anthropic content text
+    // Extract Anthropic content text and any tool calls. A pure tool-call
+    // turn can carry no text content at all, so only the absence of both is
+    // an error.
     let anthropic_content = anthropic_response
         .choices
         .first()
-        .and_then(|c| c.message.content.clone())
-        .ok_or_else(|| ApiError::AnthropicError {
+        .and_then(|c| c.message.content.clone());
+    let tool_calls = anthropic_response
+        .choices
+        .first()
+        .and_then(|c| c.message.tool_calls.clone());
+
+    if anthropic_content.is_none() && tool_calls.is_none() {
+        return Err(ApiError::AnthropicError {
             message: "No content in Anthropic response".to_string(),
             type_: "missing_content".to_string(),
             param: None,
             code: None,
-        })?;
+        });
+    }
 
     // Combine thinking content with Anthropic's response
-    let mut content = Vec::new();
-
-    // Add thinking block first
-    content.push(ContentBlock {
+    let mut content = vec![ContentBlock {
         content_type: "text".to_string(),
         text: thinking_content,
-    });
+    }];
+    if let Some(anthropic_content) = anthropic_content {
+        content.push(ContentBlock {
+            content_type: "text".to_string(),
+            text: anthropic_content,
+        });
+    }
 
-    // Add Anthropic response content
-    content.push(ContentBlock {
-        content_type: "text".to_string(),
-        text: anthropic_content,
-    });
+    Ok(CandidateResult {
+        content,
+        tool_calls,
+        deepseek_response,
+        anthropic_response,
+        deepseek_cost,
+        anthropic_cost,
+    })
+}
 
-    // Calculate usage
-    let anthropic_usage = AnthropicUsage {
-        input_tokens: anthropic_response.usage.input_tokens.unwrap_or(0),
-        output_tokens: anthropic_response.usage.output_tokens.unwrap_or(0),
-        total_tokens: anthropic_response.usage.total_tokens,
-        total_cost: format_cost(anthropic_cost),
+/// Decides what `StreamEvent::ToolUse`/`ToolUseEnd` a single Anthropic
+/// tool-call delta chunk should produce, given the content-block index
+/// currently open (at most one is ever open at a time, tracked in
+/// `open_tool_call`).
+///
+/// # Arguments
+///
+/// * `open_tool_call` - The content-block index currently open, if any;
+///   updated in place as this chunk opens or closes a block
+/// * `index` - The content-block index this chunk belongs to
+/// * `name` - The tool's name, present only on the chunk that opens the block
+/// * `arguments_delta` - A JSON fragment of the tool's arguments, if any
+/// * `stop` - Whether this chunk closes the block
+///
+/// # Returns
+///
+/// `(tool_use, tool_use_end)`: `tool_use` is `Some((name, arguments_delta))`
+/// whenever this chunk should be forwarded — whether it opens the block
+/// (`name` present), carries a JSON fragment, or both; a chunk that only
+/// opens the block with an empty delta must still be forwarded so the name
+/// isn't dropped. `tool_use_end` is `true` once `stop` closes the block.
+fn next_tool_call_event(
+    open_tool_call: &mut Option<usize>,
+    index: usize,
+    name: Option<String>,
+    arguments_delta: Option<String>,
+    stop: bool,
+) -> (Option<(Option<String>, String)>, bool) {
+    let name = if *open_tool_call == Some(index) {
+        None
+    } else {
+        *open_tool_call = Some(index);
+        name
     };
 
-    let deepseek_usage = DeepSeekUsage {
-        input_tokens: deepseek_response.usage.prompt_tokens,
-        output_tokens: deepseek_response.usage.completion_tokens,
-        total_tokens: deepseek_response.usage.total_tokens,
-        total_cost: format_cost(deepseek_cost),
-    };
+    let arguments_delta = arguments_delta.unwrap_or_default();
+    let tool_use = (name.is_some() || !arguments_delta.is_empty()).then_some((name, arguments_delta));
 
-    // Build response
-    let response = ApiResponse {
-        created: Utc::now(),
-        content,
-        deepseek_response: request.verbose.then(|| ExternalApiResponse {
-            status: deepseek_status,
-            headers: deepseek_headers,
-            body: serde_json::to_value(&deepseek_response).unwrap_or_default(),
-        }),
-        anthropic_response: request.verbose.then(|| ExternalApiResponse {
-            status: anthropic_status,
-            headers: anthropic_headers,
-            body: serde_json::to_value(&anthropic_response).unwrap_or_default(),
-        }),
-        combined_usage: CombinedUsage {
-            total_cost: format_cost(deepseek_cost + anthropic_cost),
-            deepseek_usage,
-            anthropic_usage,
-        },
-    };
+    if stop {
+        *open_tool_call = None;
+    }
 
-    Ok(Json(response))
+    (tool_use, stop)
 }
 
 /// Handler for streaming chat requests.
@@ -332,6 +578,110 @@ pub(crate) async fn chat_stream(
     headers: axum::http::HeaderMap,
     Json(request): Json<ApiRequest>,
 ) -> Result<SseResponse> {
+    let n = request.candidate_count();
+    if n > state.config.max_client_batch_size {
+        return Err(ApiError::BadRequest {
+            message: format!(
+                "n={} exceeds max_client_batch_size={}",
+                n, state.config.max_client_batch_size
+            ),
+        });
+    }
+
+    // Fan the N candidates' pipelines into a single channel; each candidate
+    // tags its own `Start`/`Content` events with its index (see
+    // `spawn_chat_pipeline`) so a client can demux the interleaved deltas.
+    // Each candidate's own `Usage`/`Done` are withheld here rather than
+    // forwarded, since a client would otherwise see N per-candidate totals
+    // and N "done" events with no reliable signal for when the *whole*
+    // batch has finished; the coordinator task below merges them into one
+    // `Usage` + one `Done` emitted only once every candidate completes.
+    let (tx, rx) = tokio::sync::mpsc::channel(100 * n as usize);
+    let (usage_tx, mut usage_rx) = tokio::sync::mpsc::channel::<CombinedUsage>(n as usize);
+    let coordinator_tx = tx.clone();
+    for index in 0..n {
+        let state = State(Arc::clone(&state));
+        let headers = headers.clone();
+        let request = Json(request.clone());
+        let tx = tx.clone();
+        let usage_tx = usage_tx.clone();
+        tokio::spawn(async move {
+            match spawn_chat_pipeline(state, headers, request, index).await {
+                Ok(mut candidate_rx) => {
+                    while let Some(event) = candidate_rx.recv().await {
+                        match event {
+                            StreamEvent::Usage { usage } => {
+                                let _ = usage_tx.send(usage).await;
+                            }
+                            StreamEvent::Done => {}
+                            other => {
+                                if tx.send(other).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    let _ = tx
+                        .send(StreamEvent::Error {
+                            message: format!("{:?}", err),
+                            code: 500,
+                        })
+                        .await;
+                }
+            }
+        });
+    }
+    drop(tx);
+    drop(usage_tx);
+
+    tokio::spawn(async move {
+        let mut usages = Vec::with_capacity(n as usize);
+        while let Some(usage) = usage_rx.recv().await {
+            usages.push(usage);
+        }
+        if !usages.is_empty() {
+            let _ = coordinator_tx
+                .send(StreamEvent::Usage {
+                    usage: merge_streamed_usage(&usages),
+                })
+                .await;
+        }
+        let _ = coordinator_tx.send(StreamEvent::Done).await;
+    });
+
+    let event_stream = ReceiverStream::new(rx).map(|event| {
+        let event_name = match &event {
+            StreamEvent::Start { .. } => "start",
+            StreamEvent::Content { .. } => "content",
+            StreamEvent::ToolUse { .. } => "tool_use",
+            StreamEvent::ToolUseEnd { .. } => "tool_use_end",
+            StreamEvent::Usage { .. } => "usage",
+            StreamEvent::Error { .. } => "error",
+            StreamEvent::Done => "done",
+        };
+        Ok(Event::default()
+            .event(event_name)
+            .data(serde_json::to_string(&event).unwrap_or_default()))
+    });
+
+    Ok(SseResponse::new(event_stream))
+}
+
+/// Runs a single candidate's DeepSeek→Anthropic pipeline and returns a
+/// receiver of the raw [`StreamEvent`]s it produces, tagging every
+/// `Content` event with `index` so a multi-candidate caller can demux them.
+///
+/// This is the shared core behind both [`chat_stream`]'s bespoke SSE format
+/// and [`crate::openai`]'s OpenAI-compatible chunk format: both simply format
+/// the same sequence of events differently.
+pub(crate) async fn spawn_chat_pipeline(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<ApiRequest>,
+    index: u32,
+) -> Result<tokio::sync::mpsc::Receiver<StreamEvent>> {
     // Validate system prompt
     if !request.validate_system_prompt() {
         return Err(ApiError::InvalidSystemPrompt);
@@ -350,45 +700,57 @@ pub(crate) async fn chat_stream(
 
     // Create channel for stream events
     let (tx, rx) = tokio::sync::mpsc::channel(100);
-    let tx = Arc::new(tx);
 
     // Spawn task to handle streaming
     let config = state.config.clone();
     let request_clone = request.clone();
     tokio::spawn(async move {
-        let tx = tx.clone();
-
         // Send start event
-        let _ = tx
-            .send(Ok(Event::default().event("start").data(
-                serde_json::to_string(&StreamEvent::Start {
-                    created: Utc::now(),
-                })
-                .unwrap_or_default(),
-            )))
-            .await;
+        if tx
+            .send(StreamEvent::Start {
+                candidate: index,
+                created: Utc::now(),
+            })
+            .await
+            .is_err()
+        {
+            return;
+        }
 
         // Process DeepSeek stream first
         let mut deepseek_usage = None;
         let mut complete_reasoning = String::new();
-        let mut deepseek_stream =
-            deepseek_client.chat_stream(messages.clone(), &request_clone.deepseek_config);
+        let mut deepseek_stream = deepseek_client.chat_stream(
+            messages.clone(),
+            &request_clone.deepseek_config,
+            request_clone.tools.as_deref(),
+        );
 
         // Send initial thinking tag
-        let _ = tx
-            .send(Ok(Event::default().event("content").data(
-                serde_json::to_string(&StreamEvent::Content {
-                    content: vec![ContentBlock {
-                        content_type: "text".to_string(),
-                        text: "<thinking>\n".to_string(),
-                    }],
-                })
-                .unwrap_or_default(),
-            )))
-            .await;
+        if tx
+            .send(StreamEvent::Content {
+                index,
+                content: vec![ContentBlock {
+                    content_type: "text".to_string(),
+                    text: "<thinking>\n".to_string(),
+                }],
+            })
+            .await
+            .is_err()
+        {
+            return;
+        }
 
         // Process DeepSeek stream
         while let Some(chunk) = deepseek_stream.next().await {
+            // The client disconnected (the SSE response's receiver was
+            // dropped) since our last send; stop pulling from DeepSeek
+            // instead of burning more tokens on a response nobody reads.
+            // Dropping `deepseek_stream` on return aborts the upstream call.
+            if tx.is_closed() {
+                return;
+            }
+
             match chunk {
                 Ok(response) => {
                     // Extract choice content
@@ -396,17 +758,19 @@ pub(crate) async fn chat_stream(
                         if let Some(content) = &choice.delta.content {
                             if !content.is_empty() {
                                 // Stream delta content
-                                let _ = tx
-                                    .send(Ok(Event::default().event("content").data(
-                                        serde_json::to_string(&StreamEvent::Content {
-                                            content: vec![ContentBlock {
-                                                content_type: "text_delta".to_string(),
-                                                text: content.to_string(),
-                                            }],
-                                        })
-                                        .unwrap_or_default(),
-                                    )))
-                                    .await;
+                                if tx
+                                    .send(StreamEvent::Content {
+                                        index,
+                                        content: vec![ContentBlock {
+                                            content_type: "text_delta".to_string(),
+                                            text: content.to_string(),
+                                        }],
+                                    })
+                                    .await
+                                    .is_err()
+                                {
+                                    return;
+                                }
 
                                 // Accumulate content
                                 complete_reasoning.push_str(content);
@@ -421,13 +785,10 @@ pub(crate) async fn chat_stream(
                 }
                 Err(e) => {
                     let _ = tx
-                        .send(Ok(Event::default().event("error").data(
-                            serde_json::to_string(&StreamEvent::Error {
-                                message: format!("DeepSeek stream error: {}", e),
-                                code: 500,
-                            })
-                            .unwrap_or_default(),
-                        )))
+                        .send(StreamEvent::Error {
+                            message: format!("DeepSeek stream error: {:?}", e),
+                            code: 500,
+                        })
                         .await;
                     return;
                 }
@@ -435,17 +796,25 @@ pub(crate) async fn chat_stream(
         }
 
         // Send closing thinking tag
-        let _ = tx
-            .send(Ok(Event::default().event("content").data(
-                serde_json::to_string(&StreamEvent::Content {
-                    content: vec![ContentBlock {
-                        content_type: "text".to_string(),
-                        text: "\n</thinking>".to_string(),
-                    }],
-                })
-                .unwrap_or_default(),
-            )))
-            .await;
+        if tx
+            .send(StreamEvent::Content {
+                index,
+                content: vec![ContentBlock {
+                    content_type: "text".to_string(),
+                    text: "\n</thinking>".to_string(),
+                }],
+            })
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        // The client may have disconnected while DeepSeek was reasoning;
+        // don't bother placing the (paid) Anthropic call at all in that case.
+        if tx.is_closed() {
+            return;
+        }
 
         // Prepare messages for Anthropic including thinking content
         let mut anthropic_messages = messages;
@@ -459,13 +828,25 @@ pub(crate) async fn chat_stream(
             anthropic_messages,
             request_clone.get_system_prompt().map(String::from),
             &request_clone.anthropic_config,
+            request_clone.tools.as_deref(),
+            request_clone.cache,
         );
 
         let mut total_content = String::new();
         let mut anthropic_input_tokens = 0;
         let mut anthropic_output_tokens = 0;
+        let mut anthropic_cache_write_tokens = 0;
+        let mut anthropic_cache_read_tokens = 0;
+        // Tracks the content-block index of the tool call currently being
+        // streamed, if any; only one is ever open at a time.
+        let mut open_tool_call: Option<usize> = None;
 
         while let Some(result) = anthropic_stream.next().await {
+            // Same disconnect check as the DeepSeek loop above.
+            if tx.is_closed() {
+                return;
+            }
+
             match result {
                 Ok(response) => {
                     // Process response
@@ -473,21 +854,60 @@ pub(crate) async fn chat_stream(
                         if let Some(content) = &choice.delta.content {
                             if !content.is_empty() {
                                 // Send content delta
-                                let _ = tx
-                                    .send(Ok(Event::default().event("content").data(
-                                        serde_json::to_string(&StreamEvent::Content {
-                                            content: vec![ContentBlock {
-                                                content_type: "text_delta".to_string(),
-                                                text: content.to_string(),
-                                            }],
-                                        })
-                                        .unwrap_or_default(),
-                                    )))
-                                    .await;
+                                if tx
+                                    .send(StreamEvent::Content {
+                                        index,
+                                        content: vec![ContentBlock {
+                                            content_type: "text_delta".to_string(),
+                                            text: content.to_string(),
+                                        }],
+                                    })
+                                    .await
+                                    .is_err()
+                                {
+                                    return;
+                                }
 
                                 total_content.push_str(content);
                             }
                         }
+
+                        if let Some(tool_call) = &choice.delta.tool_call {
+                            let (tool_use, tool_use_end) = next_tool_call_event(
+                                &mut open_tool_call,
+                                tool_call.index,
+                                tool_call.name.clone(),
+                                tool_call.arguments_delta.clone(),
+                                tool_call.stop,
+                            );
+
+                            if let Some((name, arguments_delta)) = tool_use {
+                                if tx
+                                    .send(StreamEvent::ToolUse {
+                                        candidate: index,
+                                        index: tool_call.index,
+                                        name,
+                                        arguments_delta,
+                                    })
+                                    .await
+                                    .is_err()
+                                {
+                                    return;
+                                }
+                            }
+
+                            if tool_use_end
+                                && tx
+                                    .send(StreamEvent::ToolUseEnd {
+                                        candidate: index,
+                                        index: tool_call.index,
+                                    })
+                                    .await
+                                    .is_err()
+                            {
+                                return;
+                            }
+                        }
                     }
 
                     // Track usage
@@ -495,17 +915,18 @@ pub(crate) async fn chat_stream(
                         anthropic_input_tokens = usage.prompt_tokens.max(anthropic_input_tokens);
                         anthropic_output_tokens =
                             usage.completion_tokens.max(anthropic_output_tokens);
+                        anthropic_cache_write_tokens =
+                            usage.cache_creation_input_tokens.max(anthropic_cache_write_tokens);
+                        anthropic_cache_read_tokens =
+                            usage.cache_read_input_tokens.max(anthropic_cache_read_tokens);
                     }
                 }
                 Err(e) => {
                     let _ = tx
-                        .send(Ok(Event::default().event("error").data(
-                            serde_json::to_string(&StreamEvent::Error {
-                                message: format!("Anthropic stream error: {}", e),
-                                code: 500,
-                            })
-                            .unwrap_or_default(),
-                        )))
+                        .send(StreamEvent::Error {
+                            message: format!("Anthropic stream error: {:?}", e),
+                            code: 500,
+                        })
                         .await;
                     return;
                 }
@@ -517,48 +938,236 @@ pub(crate) async fn chat_stream(
             let deepseek_cost = calculate_deepseek_cost(
                 deepseek_final_usage.prompt_tokens,
                 deepseek_final_usage.completion_tokens,
+                deepseek_final_usage.cached_tokens,
                 &config,
             );
 
             let anthropic_cost = calculate_anthropic_cost(
                 anthropic_input_tokens,
                 anthropic_output_tokens,
+                anthropic_cache_write_tokens,
+                anthropic_cache_read_tokens,
                 &config,
             );
 
             // Send final usage stats
             let _ = tx
-                .send(Ok(Event::default().event("usage").data(
-                    serde_json::to_string(&StreamEvent::Usage {
-                        usage: CombinedUsage {
-                            total_cost: format_cost(deepseek_cost + anthropic_cost),
-                            deepseek_usage: DeepSeekUsage {
-                                input_tokens: deepseek_final_usage.prompt_tokens,
-                                output_tokens: deepseek_final_usage.completion_tokens,
-                                total_tokens: deepseek_final_usage.total_tokens,
-                                total_cost: format_cost(deepseek_cost),
-                            },
-                            anthropic_usage: AnthropicUsage {
-                                input_tokens: anthropic_input_tokens,
-                                output_tokens: anthropic_output_tokens,
-                                total_tokens: anthropic_input_tokens + anthropic_output_tokens,
-                                total_cost: format_cost(anthropic_cost),
-                            },
+                .send(StreamEvent::Usage {
+                    usage: CombinedUsage {
+                        total_cost: format_cost(deepseek_cost + anthropic_cost),
+                        deepseek_usage: DeepSeekUsage {
+                            input_tokens: deepseek_final_usage.prompt_tokens,
+                            output_tokens: deepseek_final_usage.completion_tokens,
+                            total_tokens: deepseek_final_usage.total_tokens,
+                            total_cost: format_cost(deepseek_cost),
                         },
-                    })
-                    .unwrap_or_default(),
-                )))
+                        anthropic_usage: AnthropicUsage {
+                            input_tokens: anthropic_input_tokens,
+                            output_tokens: anthropic_output_tokens,
+                            total_tokens: anthropic_input_tokens + anthropic_output_tokens,
+                            total_cost: format_cost(anthropic_cost),
+                        },
+                    },
+                })
                 .await;
         }
 
         // Send done event
-        let _ = tx
-            .send(Ok(Event::default().event("done").data(
-                serde_json::to_string(&StreamEvent::Done).unwrap_or_default(),
-            )))
-            .await;
+        let _ = tx.send(StreamEvent::Done).await;
     });
 
-    // Return stream
-    Ok(SseResponse::new(ReceiverStream::new(rx)))
+    // Return the receiver
+    Ok(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AnthropicApiUsage, AnthropicChoice, AnthropicChoiceMessage, DeepSeekApiUsage, DeepSeekChoice, DeepSeekChoiceMessage};
+
+    fn candidate(
+        index: u32,
+        deepseek_tokens: (u32, u32, u32),
+        deepseek_cost: f64,
+        anthropic_tokens: (u32, u32, u32),
+        anthropic_cost: f64,
+    ) -> (u32, CandidateResult) {
+        (
+            index,
+            CandidateResult {
+                content: Vec::new(),
+                tool_calls: None,
+                deepseek_response: DeepSeekChatResponse {
+                    choices: vec![DeepSeekChoice {
+                        message: DeepSeekChoiceMessage { content: None },
+                    }],
+                    usage: DeepSeekApiUsage {
+                        prompt_tokens: deepseek_tokens.0,
+                        completion_tokens: deepseek_tokens.1,
+                        total_tokens: deepseek_tokens.2,
+                        cached_tokens: 0,
+                    },
+                },
+                anthropic_response: AnthropicChatResponse {
+                    choices: vec![AnthropicChoice {
+                        message: AnthropicChoiceMessage {
+                            content: None,
+                            tool_calls: None,
+                        },
+                    }],
+                    usage: AnthropicApiUsage {
+                        prompt_tokens: 0,
+                        completion_tokens: 0,
+                        total_tokens: anthropic_tokens.2,
+                        input_tokens: Some(anthropic_tokens.0),
+                        output_tokens: Some(anthropic_tokens.1),
+                        cache_creation_input_tokens: 0,
+                        cache_read_input_tokens: 0,
+                    },
+                },
+                deepseek_cost,
+                anthropic_cost,
+            },
+        )
+    }
+
+    #[test]
+    fn merge_candidate_usage_sums_tokens_and_costs_across_candidates() {
+        let candidates = vec![
+            candidate(0, (10, 20, 30), 0.01, (5, 15, 20), 0.02),
+            candidate(1, (11, 21, 32), 0.03, (6, 16, 22), 0.04),
+        ];
+
+        let (deepseek_usage, anthropic_usage, total_deepseek_cost, total_anthropic_cost) =
+            merge_candidate_usage(&candidates);
+
+        assert_eq!(deepseek_usage.input_tokens, 21);
+        assert_eq!(deepseek_usage.output_tokens, 41);
+        assert_eq!(deepseek_usage.total_tokens, 62);
+        assert_eq!(anthropic_usage.input_tokens, 11);
+        assert_eq!(anthropic_usage.output_tokens, 31);
+        assert_eq!(anthropic_usage.total_tokens, 42);
+        assert!((total_deepseek_cost - 0.04).abs() < f64::EPSILON);
+        assert!((total_anthropic_cost - 0.06).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn tool_call_name_is_forwarded_even_with_an_empty_opening_delta() {
+        // Anthropic's `content_block_start` carries the name with no
+        // argument fragment; a naive "only send when there's a delta" check
+        // would drop the name here.
+        let mut open_tool_call = None;
+        let (tool_use, tool_use_end) = next_tool_call_event(
+            &mut open_tool_call,
+            0,
+            Some("get_weather".to_string()),
+            None,
+            false,
+        );
+
+        assert_eq!(
+            tool_use,
+            Some((Some("get_weather".to_string()), String::new()))
+        );
+        assert!(!tool_use_end);
+        assert_eq!(open_tool_call, Some(0));
+    }
+
+    #[test]
+    fn later_chunks_for_the_same_block_omit_the_name() {
+        let mut open_tool_call = Some(0);
+        let (tool_use, tool_use_end) = next_tool_call_event(
+            &mut open_tool_call,
+            0,
+            Some("get_weather".to_string()),
+            Some("{\"loc".to_string()),
+            false,
+        );
+
+        assert_eq!(tool_use, Some((None, "{\"loc".to_string())));
+        assert!(!tool_use_end);
+    }
+
+    #[test]
+    fn empty_delta_on_an_already_open_block_is_not_forwarded() {
+        let mut open_tool_call = Some(0);
+        let (tool_use, tool_use_end) =
+            next_tool_call_event(&mut open_tool_call, 0, None, Some(String::new()), false);
+
+        assert_eq!(tool_use, None);
+        assert!(!tool_use_end);
+    }
+
+    #[test]
+    fn stop_closes_the_block_and_clears_open_tool_call() {
+        let mut open_tool_call = Some(0);
+        let (_, tool_use_end) =
+            next_tool_call_event(&mut open_tool_call, 0, None, Some(String::new()), true);
+
+        assert!(tool_use_end);
+        assert_eq!(open_tool_call, None);
+    }
+
+    #[test]
+    fn a_new_index_opens_its_own_block_while_the_old_one_stays_closed() {
+        let mut open_tool_call = Some(0);
+        let (tool_use, _) = next_tool_call_event(
+            &mut open_tool_call,
+            1,
+            Some("get_time".to_string()),
+            None,
+            false,
+        );
+
+        assert_eq!(tool_use, Some((Some("get_time".to_string()), String::new())));
+        assert_eq!(open_tool_call, Some(1));
+    }
+
+    #[test]
+    fn merge_streamed_usage_sums_tokens_and_parses_costs_across_candidates() {
+        let usages = vec![
+            CombinedUsage {
+                total_cost: "$0.030".to_string(),
+                deepseek_usage: DeepSeekUsage {
+                    input_tokens: 10,
+                    output_tokens: 20,
+                    total_tokens: 30,
+                    total_cost: "$0.010".to_string(),
+                },
+                anthropic_usage: AnthropicUsage {
+                    input_tokens: 5,
+                    output_tokens: 15,
+                    total_tokens: 20,
+                    total_cost: "$0.020".to_string(),
+                },
+            },
+            CombinedUsage {
+                total_cost: "$0.070".to_string(),
+                deepseek_usage: DeepSeekUsage {
+                    input_tokens: 11,
+                    output_tokens: 21,
+                    total_tokens: 32,
+                    total_cost: "$0.030".to_string(),
+                },
+                anthropic_usage: AnthropicUsage {
+                    input_tokens: 6,
+                    output_tokens: 16,
+                    total_tokens: 22,
+                    total_cost: "$0.040".to_string(),
+                },
+            },
+        ];
+
+        let merged = merge_streamed_usage(&usages);
+
+        assert_eq!(merged.deepseek_usage.input_tokens, 21);
+        assert_eq!(merged.deepseek_usage.output_tokens, 41);
+        assert_eq!(merged.deepseek_usage.total_tokens, 62);
+        assert_eq!(merged.deepseek_usage.total_cost, "$0.040");
+        assert_eq!(merged.anthropic_usage.input_tokens, 11);
+        assert_eq!(merged.anthropic_usage.output_tokens, 31);
+        assert_eq!(merged.anthropic_usage.total_tokens, 42);
+        assert_eq!(merged.anthropic_usage.total_cost, "$0.060");
+        assert_eq!(merged.total_cost, "$0.100");
+    }
 }
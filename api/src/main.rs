@@ -0,0 +1,24 @@
+//! deepclaude: chains a DeepSeek reasoning pass into an Anthropic response,
+//! exposing the combined result over a bespoke HTTP API.
+
+mod clients;
+mod config;
+mod error;
+mod handlers;
+mod models;
+mod openai;
+
+use config::Config;
+use handlers::AppState;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() {
+    let state = Arc::new(AppState {
+        config: Config::default(),
+    });
+    let app = handlers::router(state);
+
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
@@ -0,0 +1,344 @@
+//! Request/response types shared between the handlers and the upstream
+//! clients.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single chat message role, mirroring the OpenAI/Anthropic convention.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// A single chat message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+/// Per-provider generation settings forwarded to the upstream client.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub model: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+/// A function tool the model may call, following the OpenAI/Anthropic
+/// function-calling shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolFunction {
+    pub name: String,
+    pub description: Option<String>,
+    /// JSON schema describing the function's arguments.
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tool {
+    #[serde(rename = "type", default = "default_tool_type")]
+    pub tool_type: String,
+    pub function: ToolFunction,
+}
+
+fn default_tool_type() -> String {
+    "function".to_string()
+}
+
+/// A fully assembled tool invocation, either returned directly by the
+/// non-streaming `chat` handler or reassembled by a client from the
+/// `StreamEvent::ToolUse` deltas of `chat_stream`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "default_tool_type")]
+    pub call_type: String,
+    pub name: String,
+    /// JSON-encoded arguments.
+    pub arguments: String,
+}
+
+/// The bespoke request body accepted by `handle_chat`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiRequest {
+    pub system: Option<String>,
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub verbose: bool,
+    #[serde(default)]
+    pub deepseek_config: ProviderConfig,
+    #[serde(default)]
+    pub anthropic_config: ProviderConfig,
+    /// Tools the Anthropic response step may call. DeepSeek is given the
+    /// same definitions so its `<thinking>` block can reason about which
+    /// tool, if any, applies.
+    #[serde(default)]
+    pub tools: Option<Vec<Tool>>,
+    /// How many independent reasoning+response candidates to generate.
+    /// Defaults to 1; capped by `Config::max_client_batch_size`.
+    pub n: Option<u32>,
+    /// Whether to mark the system prompt and the `<thinking>` block as
+    /// `cache_control: {"type": "ephemeral"}` breakpoints when forwarding to
+    /// Anthropic. Worthwhile whenever the same long reasoning chain is
+    /// likely to be re-sent, e.g. across turns of a conversation.
+    #[serde(default)]
+    pub cache: bool,
+}
+
+impl ApiRequest {
+    /// A system prompt is valid as long as it is either absent or non-empty.
+    pub fn validate_system_prompt(&self) -> bool {
+        self.system.as_ref().map(|s| !s.trim().is_empty()).unwrap_or(true)
+    }
+
+    /// Number of independent candidates requested, defaulting to (and never
+    /// going below) 1.
+    pub fn candidate_count(&self) -> u32 {
+        self.n.unwrap_or(1).max(1)
+    }
+
+    pub fn get_system_prompt(&self) -> Option<&str> {
+        self.system.as_deref()
+    }
+
+    /// Returns `messages`, prefixed with the system prompt as a `System`
+    /// message when one was supplied.
+    pub fn get_messages_with_system(&self) -> Vec<Message> {
+        let mut messages = Vec::with_capacity(self.messages.len() + 1);
+        if let Some(system) = &self.system {
+            messages.push(Message {
+                role: Role::System,
+                content: system.clone(),
+            });
+        }
+        messages.extend(self.messages.iter().cloned());
+        messages
+    }
+}
+
+/// A single returned content block (either the `<thinking>` block or the
+/// final response text).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentBlock {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub text: String,
+}
+
+/// A captured upstream HTTP call, only returned when `verbose` is set.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalApiResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepSeekUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub total_cost: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnthropicUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub total_cost: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CombinedUsage {
+    pub total_cost: String,
+    pub deepseek_usage: DeepSeekUsage,
+    pub anthropic_usage: AnthropicUsage,
+}
+
+/// One candidate out of an `n`-candidate batch, mirroring the indexed
+/// `choices` array of a multi-choice completion response.
+#[derive(Debug, Serialize)]
+pub struct ApiChoice {
+    pub index: u32,
+    pub content: Vec<ContentBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    pub finish_reason: &'static str,
+}
+
+/// The bespoke response body returned by the non-streaming `chat` handler.
+#[derive(Debug, Serialize)]
+pub struct ApiResponse {
+    pub created: DateTime<Utc>,
+    pub content: Vec<ContentBlock>,
+    pub deepseek_response: Option<ExternalApiResponse>,
+    pub anthropic_response: Option<ExternalApiResponse>,
+    pub combined_usage: CombinedUsage,
+    /// Fully-assembled tool calls from the Anthropic response step, present
+    /// only when the request supplied `tools` and the model decided to
+    /// invoke one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Present whenever the request asked for more than one candidate
+    /// (`n > 1`); `content`/`tool_calls` above still mirror candidate 0 for
+    /// callers that only look at the single-candidate shape, while
+    /// `combined_usage` sums every candidate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<ApiChoice>>,
+}
+
+/// Events emitted over the `chat_stream` SSE connection.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event")]
+pub enum StreamEvent {
+    /// `candidate` identifies which of the `n` requested candidates this
+    /// pipeline run belongs to, same as `Content`'s `index`.
+    Start { candidate: u32, created: DateTime<Utc> },
+    /// `index` identifies which of the `n` requested candidates this delta
+    /// belongs to, so a client receiving interleaved streams can demux them.
+    Content {
+        index: u32,
+        content: Vec<ContentBlock>,
+    },
+    /// A partial tool-call argument chunk for the content block at `index`
+    /// of the `n`-candidate batch identified by `candidate`, so a client
+    /// demuxing interleaved candidates knows which one this belongs to.
+    /// `name` is set once, on the chunk that opens the block; every chunk
+    /// for that index carries a JSON fragment in `arguments_delta` (empty on
+    /// an opening chunk that has none) that the caller concatenates in order. Only one
+    /// block per index is ever open at a time, per candidate.
+    ToolUse {
+        candidate: u32,
+        index: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        arguments_delta: String,
+    },
+    /// Marks the end of the tool-call block at `index` of candidate
+    /// `candidate`; its arguments are now complete.
+    ToolUseEnd { candidate: u32, index: usize },
+    Usage { usage: CombinedUsage },
+    Error { message: String, code: u16 },
+    Done,
+}
+
+/// Token usage reported by DeepSeek's OpenAI-compatible API.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DeepSeekApiUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Prompt tokens served from DeepSeek's own cache, billed at
+    /// `input_cache_hit_price` instead of `input_cache_miss_price`.
+    #[serde(default, rename = "prompt_cache_hit_tokens")]
+    pub cached_tokens: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeepSeekChoiceMessage {
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeepSeekChoice {
+    pub message: DeepSeekChoiceMessage,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeepSeekChatResponse {
+    pub choices: Vec<DeepSeekChoice>,
+    pub usage: DeepSeekApiUsage,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeepSeekDelta {
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeepSeekStreamChoice {
+    pub delta: DeepSeekDelta,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeepSeekStreamChunk {
+    pub choices: Vec<DeepSeekStreamChoice>,
+    pub usage: Option<DeepSeekApiUsage>,
+}
+
+/// Token usage reported by Anthropic's API. `input_tokens`/`output_tokens`
+/// mirror Anthropic's native field names; `prompt_tokens`/`completion_tokens`/
+/// `total_tokens` use the same names as `DeepSeekApiUsage`, and
+/// `prompt_tokens`/`completion_tokens` are what `calculate_anthropic_cost` bills.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AnthropicApiUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+    /// Tokens written to the cache by this request's breakpoints.
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Tokens served from a prior cache write.
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicChoiceMessage {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicChoice {
+    pub message: AnthropicChoiceMessage,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnthropicChatResponse {
+    pub choices: Vec<AnthropicChoice>,
+    pub usage: AnthropicApiUsage,
+}
+
+/// A partial tool-call update within an Anthropic stream chunk, mirroring
+/// Anthropic's `content_block_start` / `input_json_delta` / `content_block_stop`
+/// events for a `tool_use` content block.
+#[derive(Debug, Deserialize)]
+pub struct AnthropicToolCallDelta {
+    pub index: usize,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments_delta: Option<String>,
+    #[serde(default)]
+    pub stop: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicDelta {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_call: Option<AnthropicToolCallDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicStreamChoice {
+    pub delta: AnthropicDelta,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicStreamChunk {
+    pub choices: Vec<AnthropicStreamChoice>,
+    pub usage: Option<AnthropicApiUsage>,
+}
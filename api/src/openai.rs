@@ -0,0 +1,477 @@
+//! OpenAI-compatible endpoints: `/v1/chat/completions` and `/v1/models`.
+//!
+//! `/v1/chat/completions` sits in front of the same
+//! DeepSeek-reasoning-then-Anthropic-response pipeline used by
+//! [`crate::handlers::handle_chat`], but speaks the request and response
+//! shapes that existing OpenAI SDKs (and anything built on top of them,
+//! like LangChain) already understand. The `<thinking>` block produced by
+//! the reasoning pass is folded into a `reasoning_content` field rather than
+//! dropped.
+//!
+//! `/v1/models` lets clients discover which reasoner/responder pair
+//! deepclaude runs and what it charges for them, straight from
+//! `Config.pricing`.
+
+use crate::{
+    error::Result,
+    handlers::{self, AppState},
+    models::{ApiRequest, Message, ProviderConfig, Role, StreamEvent, Tool, ToolCall},
+};
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, sync::Arc};
+use tokio_stream::wrappers::ReceiverStream;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/models", get(list_models))
+}
+
+/// A single OpenAI-shaped chat message.
+///
+/// Known limitation: there is no `tool_call_id` field, so a client cannot
+/// round-trip a tool's result back as a `"tool"`-role message the way the
+/// OpenAI function-calling protocol expects after it receives a
+/// `tool_calls` response — see `into_api_request`'s role mapping.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiChatRequest {
+    pub model: String,
+    pub messages: Vec<OpenAiMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Function tools the model may call, in the same shape OpenAI's own API
+    /// accepts; forwarded through to the underlying pipeline unchanged.
+    #[serde(default)]
+    pub tools: Option<Vec<Tool>>,
+}
+
+impl OpenAiChatRequest {
+    /// Splits out a leading `system` message (if any) and converts the rest
+    /// into an internal [`ApiRequest`].
+    fn into_api_request(self) -> ApiRequest {
+        let mut system = None;
+        let mut messages = Vec::with_capacity(self.messages.len());
+
+        for message in self.messages {
+            // `Role` has no variant for OpenAI's `"tool"` role, and
+            // `OpenAiMessage` carries no `tool_call_id` to associate a result
+            // with the call it answers, so a tool result is folded into
+            // `Role::User` like any other non-system/assistant role rather
+            // than threaded through specially. This means the pipeline can
+            // surface a tool call to the client but can't correctly continue
+            // the conversation once the client replies with the tool's
+            // result — fixing that needs `Role`/`Message` to gain a
+            // tool-result representation, which is out of scope here.
+            let role = match message.role.as_str() {
+                "system" => {
+                    system = Some(message.content);
+                    continue;
+                }
+                "assistant" => Role::Assistant,
+                _ => Role::User,
+            };
+            messages.push(Message {
+                role,
+                content: message.content,
+            });
+        }
+
+        // `self.model` is deliberately NOT copied into `provider_config.model`:
+        // `/v1/models` lists `deepseek-reasoner` and `claude-3-sonnet` as two
+        // separate ids, but a single OpenAI `model` field can't say which
+        // provider it's meant for. Forcing it onto both configs would
+        // silently misconfigure whichever one the client didn't intend.
+        // Both configs leave `model` unset instead; `model` is
+        // only used by `chat_completions` to echo the requested id back in
+        // the response.
+        let provider_config = ProviderConfig {
+            model: None,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+        };
+
+        ApiRequest {
+            system,
+            messages,
+            stream: self.stream,
+            verbose: false,
+            deepseek_config: provider_config.clone(),
+            anthropic_config: provider_config,
+            tools: self.tools,
+            n: None,
+            cache: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// A fully-assembled tool call, translated from the internal [`ToolCall`]
+/// into OpenAI's nested `function: { name, arguments }` wire shape.
+#[derive(Debug, Serialize)]
+pub struct OpenAiToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: OpenAiToolCallFunction,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiToolCallFunction {
+    pub name: String,
+    pub arguments: String,
+}
+
+impl From<&ToolCall> for OpenAiToolCall {
+    fn from(tool_call: &ToolCall) -> Self {
+        Self {
+            id: tool_call.id.clone(),
+            call_type: tool_call.call_type.clone(),
+            function: OpenAiToolCallFunction {
+                name: tool_call.name.clone(),
+                arguments: tool_call.arguments.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiResponseMessage {
+    pub role: &'static str,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OpenAiToolCall>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChoice {
+    pub index: u32,
+    pub message: OpenAiResponseMessage,
+    pub finish_reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChatResponse {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<OpenAiChoice>,
+    pub usage: OpenAiUsage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OpenAiToolCallDelta>>,
+}
+
+/// A partial tool-call chunk in OpenAI's streaming shape: `id`/`type`/
+/// `function.name` are only present on the chunk that opens the block,
+/// `function.arguments` carries a JSON fragment on every chunk.
+#[derive(Debug, Serialize)]
+pub struct OpenAiToolCallDelta {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub call_type: Option<&'static str>,
+    pub function: OpenAiToolCallFunctionDelta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiToolCallFunctionDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub arguments: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChunkChoice {
+    pub index: u32,
+    pub delta: OpenAiDelta,
+    pub finish_reason: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChatChunk {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<OpenAiChunkChoice>,
+}
+
+/// Handler for `/v1/chat/completions`.
+///
+/// Translates the OpenAI-shaped request into the existing DeepSeek→Anthropic
+/// pipeline and translates the result back, either as a single JSON object
+/// or as `text/event-stream` chunks depending on `stream`.
+pub(crate) async fn chat_completions(
+    state: State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<OpenAiChatRequest>,
+) -> Result<Response> {
+    let model = request.model.clone();
+    let stream = request.stream;
+    let api_request = request.into_api_request();
+
+    if stream {
+        let sse = stream_chat_completions(state, headers, api_request, model).await?;
+        Ok(sse.into_response())
+    } else {
+        let json = complete_chat_completions(state, headers, api_request, model).await?;
+        Ok(Json(json).into_response())
+    }
+}
+
+async fn complete_chat_completions(
+    state: State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    api_request: ApiRequest,
+    model: String,
+) -> Result<OpenAiChatResponse> {
+    let response = handlers::chat(state, headers, Json(api_request)).await?.0;
+
+    let reasoning_content = response
+        .content
+        .first()
+        .map(|block| block.text.trim_start_matches("<thinking>\n").trim_end_matches("\n</thinking>").to_string());
+    let content = response
+        .content
+        .get(1)
+        .map(|block| block.text.clone())
+        .unwrap_or_default();
+
+    let tool_calls = response
+        .tool_calls
+        .as_ref()
+        .map(|calls| calls.iter().map(OpenAiToolCall::from).collect::<Vec<_>>());
+    let finish_reason = if tool_calls.is_some() { "tool_calls" } else { "stop" };
+
+    Ok(OpenAiChatResponse {
+        id: format!("chatcmpl-{}", response.created.timestamp_micros()),
+        object: "chat.completion",
+        created: response.created.timestamp(),
+        model,
+        choices: vec![OpenAiChoice {
+            index: 0,
+            message: OpenAiResponseMessage {
+                role: "assistant",
+                content,
+                reasoning_content,
+                tool_calls,
+            },
+            finish_reason,
+        }],
+        usage: OpenAiUsage {
+            prompt_tokens: response.combined_usage.deepseek_usage.input_tokens
+                + response.combined_usage.anthropic_usage.input_tokens,
+            completion_tokens: response.combined_usage.deepseek_usage.output_tokens
+                + response.combined_usage.anthropic_usage.output_tokens,
+            total_tokens: response.combined_usage.deepseek_usage.total_tokens
+                + response.combined_usage.anthropic_usage.total_tokens,
+        },
+    })
+}
+
+async fn stream_chat_completions(
+    state: State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    api_request: ApiRequest,
+    model: String,
+) -> Result<Sse<ReceiverStream<std::result::Result<Event, Infallible>>>> {
+    let mut pipeline_rx =
+        handlers::spawn_chat_pipeline(state, headers, Json(api_request), 0).await?;
+
+    let id = format!("chatcmpl-{}", Utc::now().timestamp_micros());
+    let (tx, rx) = tokio::sync::mpsc::channel(100);
+
+    tokio::spawn(async move {
+        let mut sent_role = false;
+        let mut in_thinking = false;
+
+        while let Some(stream_event) = pipeline_rx.recv().await {
+            let (content_delta, reasoning_delta, tool_calls_delta) = match stream_event {
+                StreamEvent::Content { content, .. } => {
+                    let mut content_delta = None;
+                    let mut reasoning_delta = None;
+                    for block in content {
+                        if block.text.starts_with("<thinking>") {
+                            in_thinking = true;
+                            continue;
+                        }
+                        if block.text.starts_with("\n</thinking>") {
+                            in_thinking = false;
+                            continue;
+                        }
+                        if in_thinking {
+                            reasoning_delta = Some(block.text);
+                        } else {
+                            content_delta = Some(block.text);
+                        }
+                    }
+                    (content_delta, reasoning_delta, None)
+                }
+                StreamEvent::ToolUse {
+                    index,
+                    name,
+                    arguments_delta,
+                    ..
+                } => {
+                    let tool_call = OpenAiToolCallDelta {
+                        index,
+                        id: name.is_some().then(|| format!("call_{}", index)),
+                        call_type: name.is_some().then_some("function"),
+                        function: OpenAiToolCallFunctionDelta {
+                            name,
+                            arguments: arguments_delta,
+                        },
+                    };
+                    (None, None, Some(vec![tool_call]))
+                }
+                StreamEvent::ToolUseEnd { .. } => continue,
+                StreamEvent::Done => break,
+                _ => continue,
+            };
+
+            let delta = OpenAiDelta {
+                role: (!sent_role).then_some("assistant"),
+                content: content_delta,
+                reasoning_content: reasoning_delta,
+                tool_calls: tool_calls_delta,
+            };
+            sent_role = true;
+
+            let chunk = OpenAiChatChunk {
+                id: id.clone(),
+                object: "chat.completion.chunk",
+                created: Utc::now().timestamp(),
+                model: model.clone(),
+                choices: vec![OpenAiChunkChoice {
+                    index: 0,
+                    delta,
+                    finish_reason: None,
+                }],
+            };
+            if tx
+                .send(Ok(Event::default().data(serde_json::to_string(&chunk).unwrap_or_default())))
+                .await
+                .is_err()
+            {
+                // Client disconnected: drop `pipeline_rx` so the underlying
+                // pipeline's next `tx.is_closed()` check stops it too.
+                return;
+            }
+        }
+
+        let _ = tx.send(Ok(Event::default().data("[DONE]"))).await;
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx)))
+}
+
+/// Per-million-token pricing for a single model, as configured in
+/// `Config.pricing`. The cache fields are provider-specific (DeepSeek only
+/// has a cache-hit discount; Anthropic bills cache writes and reads
+/// separately) so each is only present for the model it applies to.
+#[derive(Debug, Serialize)]
+pub struct ModelPricingInfo {
+    pub input_price: f64,
+    pub output_price: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_cache_hit_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read_price: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelInfo {
+    pub id: &'static str,
+    pub object: &'static str,
+    pub created: i64,
+    pub owned_by: &'static str,
+    pub pricing: ModelPricingInfo,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelList {
+    pub object: &'static str,
+    pub data: Vec<ModelInfo>,
+}
+
+/// Handler for `/v1/models`.
+///
+/// Lists the reasoner/responder pair deepclaude chains together, with the
+/// same per-million-token prices `calculate_deepseek_cost` and
+/// `calculate_anthropic_cost` use, so callers can see the cost of a request
+/// before sending one.
+pub(crate) async fn list_models(State(state): State<Arc<AppState>>) -> Json<ModelList> {
+    let created = Utc::now().timestamp();
+    let pricing = &state.config.pricing;
+
+    Json(ModelList {
+        object: "list",
+        data: vec![
+            ModelInfo {
+                id: "deepseek-reasoner",
+                object: "model",
+                created,
+                owned_by: "deepclaude",
+                pricing: ModelPricingInfo {
+                    input_price: pricing.deepseek.input_cache_miss_price,
+                    output_price: pricing.deepseek.output_price,
+                    input_cache_hit_price: Some(pricing.deepseek.input_cache_hit_price),
+                    cache_write_price: None,
+                    cache_read_price: None,
+                },
+            },
+            ModelInfo {
+                id: "claude-3-sonnet",
+                object: "model",
+                created,
+                owned_by: "deepclaude",
+                pricing: ModelPricingInfo {
+                    input_price: pricing.anthropic.claude_3_sonnet.input_price,
+                    output_price: pricing.anthropic.claude_3_sonnet.output_price,
+                    input_cache_hit_price: None,
+                    cache_write_price: Some(pricing.anthropic.claude_3_sonnet.cache_write_price),
+                    cache_read_price: Some(pricing.anthropic.claude_3_sonnet.cache_read_price),
+                },
+            },
+        ],
+    })
+}